@@ -4,16 +4,33 @@ use std::collections::HashMap;
 use once_cell::sync::Lazy;
 
 /// UTF-16 byte level decoder
-/// 
+///
 /// This decoder is to be used in tandem with the UTF16ByteLevel PreTokenizer.
 /// It converts UTF-16 byte-level tokens back to their original UTF-8 string representation.
-/// 
+///
 /// Author: Hyunsik Kim <avantkim@gmail.com>
 /// Date: May 2025
-/// 
+///
 /// This implementation is based on the original ByteLevel decoder from the tokenizers library
 /// but adapted to work with UTF-16 encoding instead of UTF-8.
 
+/// Byte order used when interpreting the two bytes of each UTF-16 code unit.
+///
+/// Mirrors the explicit-endian split that the standard library exposes through
+/// `from_utf16le`/`from_utf16be`: a vocabulary trained on one byte order can never
+/// round-trip through the other, so this must be picked per-model.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ByteOrder {
+    LittleEndian,
+    BigEndian,
+}
+
+impl Default for ByteOrder {
+    fn default() -> Self {
+        ByteOrder::LittleEndian
+    }
+}
+
 /// Converts UTF-16 bytes to unicode characters for UTF-16 byte level encoding.
 /// Same mapping as used in the pre-tokenizer.
 fn utf16_bytes_char() -> HashMap<u8, char> {
@@ -39,22 +56,179 @@ fn utf16_bytes_char() -> HashMap<u8, char> {
         .collect()
 }
 
-/// Converts UTF-16 bytes back to UTF-8 string
-fn utf16_bytes_to_utf8(bytes: &[u8]) -> Result<String> {
-    if bytes.len() % 2 != 0 {
-        return Err("Invalid UTF-16 byte sequence: odd number of bytes".into());
+/// The specific way a UTF-16 byte sequence failed to decode.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Utf16DecodeErrorKind {
+    /// A high surrogate (0xD800-0xDBFF) was not followed by a matching low surrogate.
+    UnpairedHighSurrogate,
+    /// A low surrogate (0xDC00-0xDFFF) appeared without a preceding high surrogate.
+    UnpairedLowSurrogate,
+    /// The byte sequence had an odd length, leaving one byte with no pair.
+    TrailingOddByte,
+}
+
+/// Structured error describing exactly where and why UTF-16 decoding failed.
+///
+/// Unlike a plain error string, this carries `valid_prefix` (the text already decoded
+/// before the failure) and `valid_up_to` (the byte offset of the failure), so a caller
+/// can keep the good portion and decide how to handle the tail, following the
+/// `DecodeError::{Invalid, Incomplete}` design used by streaming UTF decoders.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Utf16DecodeError {
+    pub valid_prefix: String,
+    pub valid_up_to: usize,
+    pub kind: Utf16DecodeErrorKind,
+}
+
+impl std::fmt::Display for Utf16DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self.kind {
+            Utf16DecodeErrorKind::UnpairedHighSurrogate => "unpaired high surrogate",
+            Utf16DecodeErrorKind::UnpairedLowSurrogate => "unpaired low surrogate",
+            Utf16DecodeErrorKind::TrailingOddByte => "trailing odd byte",
+        };
+        write!(
+            f,
+            "Invalid UTF-16 sequence at byte offset {}: {}",
+            self.valid_up_to, reason
+        )
     }
-    
-    let mut utf16_units = Vec::with_capacity(bytes.len() / 2);
-    
-    for chunk in bytes.chunks_exact(2) {
-        // Little-endian decoding
-        let unit = u16::from_le_bytes([chunk[0], chunk[1]]);
-        utf16_units.push(unit);
+}
+
+impl std::error::Error for Utf16DecodeError {}
+
+/// Converts UTF-16 bytes back to UTF-8 string, honoring the configured byte order and
+/// optional BOM handling.
+///
+/// When `handle_bom` is set, a leading U+FEFF is stripped, and a leading U+FFFE (the
+/// byte-swapped BOM) flips `byte_order` for the remainder of the stream.
+///
+/// When `lossy` is set, malformed input is recovered instead of rejected: a dangling
+/// high surrogate, a stray low surrogate, or a single trailing odd byte each become one
+/// U+FFFD and decoding continues, mirroring `from_utf16le_lossy`/`from_utf16be_lossy`.
+/// Otherwise, a malformed sequence fails with a [`Utf16DecodeError`].
+pub(crate) fn utf16_bytes_to_utf8(
+    bytes: &[u8],
+    byte_order: ByteOrder,
+    handle_bom: bool,
+    lossy: bool,
+) -> Result<String> {
+    let mut chunks = bytes.chunks_exact(2);
+    let trailing_odd_byte = !chunks.remainder().is_empty();
+    let mut effective_order = byte_order;
+
+    let mut decoded = String::new();
+    let mut pending_high: Option<(u16, usize)> = None;
+    let mut offset = 0usize;
+    let mut first = true;
+
+    for chunk in chunks {
+        let unit = decode_unit(chunk, effective_order);
+
+        if first {
+            first = false;
+            if handle_bom {
+                match unit {
+                    0xFEFF => {
+                        offset += 2;
+                        continue;
+                    }
+                    0xFFFE => {
+                        effective_order = flip(effective_order);
+                        offset += 2;
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if let Some((high, high_offset)) = pending_high.take() {
+            if (0xDC00..=0xDFFF).contains(&unit) {
+                decoded.push(decode_surrogate_pair(high, unit));
+                offset += 2;
+                continue;
+            } else if lossy {
+                decoded.push(char::REPLACEMENT_CHARACTER);
+            } else {
+                return Err(Utf16DecodeError {
+                    valid_prefix: decoded,
+                    valid_up_to: high_offset,
+                    kind: Utf16DecodeErrorKind::UnpairedHighSurrogate,
+                }
+                .into());
+            }
+        }
+
+        if (0xD800..=0xDBFF).contains(&unit) {
+            pending_high = Some((unit, offset));
+        } else if (0xDC00..=0xDFFF).contains(&unit) {
+            if lossy {
+                decoded.push(char::REPLACEMENT_CHARACTER);
+            } else {
+                return Err(Utf16DecodeError {
+                    valid_prefix: decoded,
+                    valid_up_to: offset,
+                    kind: Utf16DecodeErrorKind::UnpairedLowSurrogate,
+                }
+                .into());
+            }
+        } else {
+            // Safety: `unit` is neither a high nor a low surrogate here, so it is a
+            // valid standalone UTF-16 code unit and maps to a valid `char`.
+            decoded.push(unsafe { std::char::from_u32_unchecked(unit as u32) });
+        }
+        offset += 2;
+    }
+
+    if let Some((_, high_offset)) = pending_high {
+        if lossy {
+            decoded.push(char::REPLACEMENT_CHARACTER);
+        } else {
+            return Err(Utf16DecodeError {
+                valid_prefix: decoded,
+                valid_up_to: high_offset,
+                kind: Utf16DecodeErrorKind::UnpairedHighSurrogate,
+            }
+            .into());
+        }
+    }
+
+    if trailing_odd_byte {
+        if lossy {
+            decoded.push(char::REPLACEMENT_CHARACTER);
+        } else {
+            return Err(Utf16DecodeError {
+                valid_prefix: decoded,
+                valid_up_to: bytes.len() - 1,
+                kind: Utf16DecodeErrorKind::TrailingOddByte,
+            }
+            .into());
+        }
+    }
+
+    Ok(decoded)
+}
+
+fn decode_unit(chunk: &[u8], order: ByteOrder) -> u16 {
+    match order {
+        ByteOrder::LittleEndian => u16::from_le_bytes([chunk[0], chunk[1]]),
+        ByteOrder::BigEndian => u16::from_be_bytes([chunk[0], chunk[1]]),
+    }
+}
+
+fn decode_surrogate_pair(high: u16, low: u16) -> char {
+    let c = 0x10000 + ((high as u32 - 0xD800) << 10) + (low as u32 - 0xDC00);
+    // Safety: combining a high and low surrogate this way always yields a valid
+    // non-BMP scalar value in 0x10000..=0x10FFFF.
+    unsafe { std::char::from_u32_unchecked(c) }
+}
+
+fn flip(order: ByteOrder) -> ByteOrder {
+    match order {
+        ByteOrder::LittleEndian => ByteOrder::BigEndian,
+        ByteOrder::BigEndian => ByteOrder::LittleEndian,
     }
-    
-    String::from_utf16(&utf16_units)
-        .map_err(|e| format!("Invalid UTF-16 sequence: {}", e).into())
 }
 
 static CHAR_UTF16_BYTES: Lazy<HashMap<char, u8>> =
@@ -62,39 +236,256 @@ static CHAR_UTF16_BYTES: Lazy<HashMap<char, u8>> =
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 /// UTF16ByteLevel Decoder
-/// 
+///
 /// This decoder is to be used in tandem with the UTF16ByteLevel PreTokenizer.
 /// It converts UTF-16 byte-level character representations back to their original UTF-8 strings.
-pub struct UTF16ByteLevel;
+pub struct UTF16ByteLevel {
+    /// Byte order to use when reassembling UTF-16 code units. Defaults to little-endian
+    /// to keep existing models loading unchanged.
+    #[serde(default)]
+    pub byte_order: ByteOrder,
+    /// Whether to strip a leading BOM (U+FEFF), or flip `byte_order` when the leading
+    /// unit is the byte-swapped BOM (U+FFFE).
+    #[serde(default)]
+    pub handle_bom: bool,
+    /// When set, malformed UTF-16 (an unpaired surrogate or a trailing odd byte) is
+    /// replaced with U+FFFD instead of causing decoding to fail.
+    #[serde(default)]
+    pub lossy: bool,
+}
 
 impl Default for UTF16ByteLevel {
     fn default() -> Self {
-        Self
+        Self {
+            byte_order: ByteOrder::LittleEndian,
+            handle_bom: false,
+            lossy: false,
+        }
     }
 }
 
+impl UTF16ByteLevel {
+    pub fn new(byte_order: ByteOrder, handle_bom: bool, lossy: bool) -> Self {
+        Self {
+            byte_order,
+            handle_bom,
+            lossy,
+        }
+    }
+
+    #[must_use]
+    pub fn byte_order(mut self, v: ByteOrder) -> Self {
+        self.byte_order = v;
+        self
+    }
+
+    #[must_use]
+    pub fn handle_bom(mut self, v: bool) -> Self {
+        self.handle_bom = v;
+        self
+    }
+
+    #[must_use]
+    pub fn lossy(mut self, v: bool) -> Self {
+        self.lossy = v;
+        self
+    }
+}
+
+/// Maps a decoded token's chars back to the UTF-16 bytes they represent, via
+/// `CHAR_UTF16_BYTES`. Characters outside the byte-level alphabet (e.g. added special
+/// tokens) are passed through as their own UTF-8 bytes, unchanged.
+fn token_to_bytes(token: &str) -> Vec<u8> {
+    token
+        .chars()
+        .try_fold(vec![], |mut acc, c| {
+            CHAR_UTF16_BYTES.get(&c).map(|b| {
+                acc.push(*b);
+                acc
+            })
+        })
+        .unwrap_or_else(|| token.as_bytes().to_vec())
+}
+
 impl Decoder for UTF16ByteLevel {
     fn decode_chain(&self, tokens: Vec<String>) -> Result<Vec<String>> {
         let utf16_bytes = tokens
-            .into_iter()
-            .flat_map(|t| {
-                t.chars()
-                    .try_fold(vec![], |mut acc, c| {
-                        CHAR_UTF16_BYTES.get(&c).map(|b| {
-                            acc.push(*b);
-                            acc
-                        })
-                    })
-                    .unwrap_or_else(|| t.as_bytes().to_vec())
-            })
+            .iter()
+            .flat_map(|t| token_to_bytes(t))
             .collect::<Vec<u8>>();
-            
+
         // Convert UTF-16 bytes back to UTF-8 string
-        let decoded = utf16_bytes_to_utf8(&utf16_bytes)?;
+        let decoded =
+            utf16_bytes_to_utf8(&utf16_bytes, self.byte_order, self.handle_bom, self.lossy)?;
         Ok(vec![decoded])
     }
 }
 
+/// Stateful decoder for token-by-token streaming generation.
+///
+/// A single `UTF16ByteLevel` token frequently ends mid code-unit or between the two
+/// halves of a surrogate pair, so decoding one token at a time with `decode_chain` would
+/// require re-decoding the whole sequence on every step. `StreamingUTF16Decoder` instead
+/// keeps the undecodable tail of each step buffered and only emits the safely decodable
+/// prefix, following the valid-prefix / incomplete-sequence pattern used by incremental
+/// UTF decoders.
+pub struct StreamingUTF16Decoder {
+    config: UTF16ByteLevel,
+    pending_bytes: Vec<u8>,
+    bom_pending: bool,
+    /// Byte offset, in the overall stream, of `pending_bytes[0]`. Carried across calls
+    /// so a `Utf16DecodeError` raised mid-stream reports `valid_up_to` against the full
+    /// stream rather than just the current `step`.
+    base_offset: usize,
+}
+
+impl StreamingUTF16Decoder {
+    pub fn new(config: UTF16ByteLevel) -> Self {
+        Self {
+            bom_pending: config.handle_bom,
+            config,
+            pending_bytes: Vec::new(),
+            base_offset: 0,
+        }
+    }
+
+    /// Feeds the next batch of tokens and returns whatever UTF-8 text can be safely
+    /// decoded so far. Bytes that end mid code-unit, or in an unpaired high surrogate,
+    /// are held back until a later `step` or `finish` completes them.
+    pub fn step(&mut self, tokens: Vec<String>) -> Result<String> {
+        for token in &tokens {
+            self.pending_bytes.extend(token_to_bytes(token));
+        }
+
+        let mut chunks = self.pending_bytes.chunks_exact(2);
+        let mut units = Vec::with_capacity(chunks.len());
+        for chunk in chunks.by_ref() {
+            units.push(decode_unit(chunk, self.config.byte_order));
+        }
+        // `units.len()` tracks how many 2-byte units we are about to consume from
+        // `pending_bytes`; any trailing odd byte is simply left buffered since it is
+        // not part of `units` in the first place.
+        let mut consumed_units = units.len();
+
+        let mut bom_skipped_units = 0usize;
+        if self.bom_pending {
+            // Only clear `bom_pending` once a complete unit is actually available to
+            // check: clearing it unconditionally would permanently skip the BOM check
+            // if the first `step` call delivered fewer than 2 bytes (e.g. a
+            // byte-at-a-time stream), leaking a literal U+FEFF into the output instead
+            // of stripping it.
+            if let Some(&first) = units.first() {
+                self.bom_pending = false;
+                match first {
+                    0xFEFF => {
+                        units.remove(0);
+                        bom_skipped_units = 1;
+                    }
+                    0xFFFE => {
+                        self.config.byte_order = flip(self.config.byte_order);
+                        // The units after the BOM were read in the old byte order.
+                        units = self.pending_bytes[2..]
+                            .chunks_exact(2)
+                            .map(|chunk| decode_unit(chunk, self.config.byte_order))
+                            .collect();
+                        bom_skipped_units = 1;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // An unpaired high surrogate at the very end might still be completed by the
+        // next step's low surrogate, so hold its two bytes back rather than decoding it.
+        let held_back_unit = matches!(units.last(), Some(&u) if (0xD800..=0xDBFF).contains(&u));
+        if held_back_unit {
+            units.pop();
+            consumed_units -= 1;
+        }
+
+        // `units` starts at `self.base_offset` plus whatever was skipped for the BOM in
+        // this step, so a `Utf16DecodeError` raised here reports an offset into the
+        // whole stream, not just this call's bytes.
+        let unit_base_offset = self.base_offset + bom_skipped_units * 2;
+        let decoded = decode_units(&units, self.config.lossy, unit_base_offset)?;
+        self.pending_bytes.drain(0..consumed_units * 2);
+        self.base_offset += consumed_units * 2;
+
+        Ok(decoded)
+    }
+
+    /// Flushes any remaining buffered bytes, completing the stream. A dangling high
+    /// surrogate or trailing odd byte is either reported as an error, or replaced with
+    /// U+FFFD when `lossy` is enabled.
+    pub fn finish(&mut self) -> Result<String> {
+        let bytes = std::mem::take(&mut self.pending_bytes);
+        if bytes.is_empty() {
+            return Ok(String::new());
+        }
+        utf16_bytes_to_utf8(&bytes, self.config.byte_order, false, self.config.lossy).map_err(
+            |e| match e.downcast::<Utf16DecodeError>() {
+                // `bytes` here is just the still-buffered tail, so re-base the reported
+                // offset onto the whole stream, same as `step` does.
+                Ok(err) => {
+                    let mut err = *err;
+                    err.valid_up_to += self.base_offset;
+                    err.into()
+                }
+                Err(other) => other,
+            },
+        )
+    }
+}
+
+/// Decodes a complete sequence of UTF-16 code units (no partial trailing byte) to a
+/// UTF-8 string, in strict or lossy mode.
+///
+/// Strict failures are reported as a [`Utf16DecodeError`] carrying `valid_prefix` (scoped
+/// to this call's `units`) and a `valid_up_to` byte offset. `base_offset` is added to
+/// that offset so, when called from `StreamingUTF16Decoder::step`, it lands on the same
+/// absolute byte position in the whole stream that `utf16_bytes_to_utf8` would report
+/// for a one-shot `decode_chain` over the same bytes — not just an offset local to the
+/// current `step` call.
+fn decode_units(units: &[u16], lossy: bool, base_offset: usize) -> Result<String> {
+    let mut decoded = String::with_capacity(units.len());
+    let mut iter = units.iter().copied().enumerate().peekable();
+    while let Some((idx, unit)) = iter.next() {
+        if (0xD800..=0xDBFF).contains(&unit) {
+            match iter.peek().copied() {
+                Some((_, low)) if (0xDC00..=0xDFFF).contains(&low) => {
+                    iter.next();
+                    decoded.push(decode_surrogate_pair(unit, low));
+                }
+                _ if lossy => decoded.push(char::REPLACEMENT_CHARACTER),
+                _ => {
+                    return Err(Utf16DecodeError {
+                        valid_prefix: decoded,
+                        valid_up_to: base_offset + idx * 2,
+                        kind: Utf16DecodeErrorKind::UnpairedHighSurrogate,
+                    }
+                    .into())
+                }
+            }
+        } else if (0xDC00..=0xDFFF).contains(&unit) {
+            if lossy {
+                decoded.push(char::REPLACEMENT_CHARACTER);
+            } else {
+                return Err(Utf16DecodeError {
+                    valid_prefix: decoded,
+                    valid_up_to: base_offset + idx * 2,
+                    kind: Utf16DecodeErrorKind::UnpairedLowSurrogate,
+                }
+                .into());
+            }
+        } else {
+            // Safety: `unit` is neither a high nor a low surrogate here, so it is a
+            // valid standalone UTF-16 code unit and maps to a valid `char`.
+            decoded.push(unsafe { std::char::from_u32_unchecked(unit as u32) });
+        }
+    }
+    Ok(decoded)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -103,27 +494,251 @@ mod tests {
     fn test_utf16_bytes_to_utf8() {
         // Test ASCII
         let bytes = vec![0x48, 0x00, 0x65, 0x00, 0x6C, 0x00, 0x6C, 0x00, 0x6F, 0x00];
-        let result = utf16_bytes_to_utf8(&bytes).unwrap();
+        let result = utf16_bytes_to_utf8(&bytes, ByteOrder::LittleEndian, false, false).unwrap();
         assert_eq!(result, "Hello");
-        
+
         // Test Korean characters
         let bytes = vec![0x00, 0xAC, 0x98, 0xB0, 0xE4, 0xB2];
-        let result = utf16_bytes_to_utf8(&bytes).unwrap();
+        let result = utf16_bytes_to_utf8(&bytes, ByteOrder::LittleEndian, false, false).unwrap();
         assert_eq!(result, "가나다");
     }
 
+    #[test]
+    fn test_utf16_bytes_to_utf8_big_endian() {
+        // "Hello" in big-endian UTF-16
+        let bytes = vec![0x00, 0x48, 0x00, 0x65, 0x00, 0x6C, 0x00, 0x6C, 0x00, 0x6F];
+        let result = utf16_bytes_to_utf8(&bytes, ByteOrder::BigEndian, false, false).unwrap();
+        assert_eq!(result, "Hello");
+    }
+
+    #[test]
+    fn test_utf16_bytes_to_utf8_strips_bom() {
+        // Little-endian BOM (U+FEFF) followed by "A" (U+0041)
+        let bytes = vec![0xFF, 0xFE, 0x41, 0x00];
+        let result = utf16_bytes_to_utf8(&bytes, ByteOrder::LittleEndian, true, false).unwrap();
+        assert_eq!(result, "A");
+    }
+
+    #[test]
+    fn test_utf16_bytes_to_utf8_swapped_bom_flips_order() {
+        // Byte-swapped BOM (U+FFFE when read as little-endian) signals the stream is
+        // actually big-endian; "A" (U+0041) follows in big-endian order.
+        let bytes = vec![0xFE, 0xFF, 0x00, 0x41];
+        let result = utf16_bytes_to_utf8(&bytes, ByteOrder::LittleEndian, true, false).unwrap();
+        assert_eq!(result, "A");
+    }
+
+    #[test]
+    fn test_lossy_dangling_high_surrogate() {
+        // High surrogate (0xD800) with no low surrogate following.
+        let bytes = vec![0x00, 0xD8, 0x41, 0x00];
+        let result = utf16_bytes_to_utf8(&bytes, ByteOrder::LittleEndian, false, true).unwrap();
+        assert_eq!(result, "\u{FFFD}A");
+    }
+
+    #[test]
+    fn test_lossy_stray_low_surrogate() {
+        // Low surrogate (0xDC00) with no preceding high surrogate.
+        let bytes = vec![0x41, 0x00, 0x00, 0xDC];
+        let result = utf16_bytes_to_utf8(&bytes, ByteOrder::LittleEndian, false, true).unwrap();
+        assert_eq!(result, "A\u{FFFD}");
+    }
+
+    #[test]
+    fn test_lossy_trailing_odd_byte() {
+        let bytes = vec![0x41, 0x00, 0x42];
+        let result = utf16_bytes_to_utf8(&bytes, ByteOrder::LittleEndian, false, true).unwrap();
+        assert_eq!(result, "A\u{FFFD}");
+    }
+
+    #[test]
+    fn test_lossy_valid_surrogate_pair_still_decodes() {
+        // 😀 = U+1F600, surrogate pair 0xD83D 0xDE00
+        let bytes = vec![0x3D, 0xD8, 0x00, 0xDE];
+        let result = utf16_bytes_to_utf8(&bytes, ByteOrder::LittleEndian, false, true).unwrap();
+        assert_eq!(result, "😀");
+    }
+
     #[test]
     fn test_decoder() {
         let decoder = UTF16ByteLevel::default();
-        
+
         // Test with simple ASCII tokens
         // This would be the result of encoding "Hello" through UTF16ByteLevel pre-tokenizer
         // Each byte of the UTF-16 representation gets mapped to a character
         let tokens = vec!["H".to_string(), "\u{0100}".to_string()]; // Example tokens
-        
+
         // Note: In practice, the tokens would be the character representations
         // of the UTF-16 bytes, but this is a simplified test
         let result = decoder.decode_chain(tokens);
         assert!(result.is_ok());
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_decode_error_reports_valid_prefix_and_offset() {
+        // "Hi" followed by a dangling high surrogate.
+        let bytes = vec![0x48, 0x00, 0x69, 0x00, 0x00, 0xD8];
+        let err = utf16_bytes_to_utf8(&bytes, ByteOrder::LittleEndian, false, false).unwrap_err();
+        let err = err.downcast_ref::<Utf16DecodeError>().unwrap();
+        assert_eq!(err.valid_prefix, "Hi");
+        assert_eq!(err.valid_up_to, 4);
+        assert_eq!(err.kind, Utf16DecodeErrorKind::UnpairedHighSurrogate);
+    }
+
+    #[test]
+    fn test_decode_error_unpaired_low_surrogate() {
+        let bytes = vec![0x48, 0x00, 0x00, 0xDC];
+        let err = utf16_bytes_to_utf8(&bytes, ByteOrder::LittleEndian, false, false).unwrap_err();
+        let err = err.downcast_ref::<Utf16DecodeError>().unwrap();
+        assert_eq!(err.valid_prefix, "H");
+        assert_eq!(err.valid_up_to, 2);
+        assert_eq!(err.kind, Utf16DecodeErrorKind::UnpairedLowSurrogate);
+    }
+
+    #[test]
+    fn test_decode_error_trailing_odd_byte() {
+        let bytes = vec![0x48, 0x00, 0x42];
+        let err = utf16_bytes_to_utf8(&bytes, ByteOrder::LittleEndian, false, false).unwrap_err();
+        let err = err.downcast_ref::<Utf16DecodeError>().unwrap();
+        assert_eq!(err.valid_prefix, "H");
+        assert_eq!(err.valid_up_to, 2);
+        assert_eq!(err.kind, Utf16DecodeErrorKind::TrailingOddByte);
+    }
+
+    /// Maps each byte to its byte-level character representation, as a one-token-per-byte
+    /// stream, for exercising `StreamingUTF16Decoder` a byte at a time.
+    fn byte_tokens(bytes: &[u8]) -> Vec<String> {
+        let bytes_char = utf16_bytes_char();
+        bytes
+            .iter()
+            .map(|b| bytes_char[b].to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_streaming_decoder_splits_mid_code_unit() {
+        let mut decoder = StreamingUTF16Decoder::new(UTF16ByteLevel::default());
+        // "Hi" = [0x48, 0x00, 0x69, 0x00], fed one byte at a time.
+        let bytes = vec![0x48, 0x00, 0x69, 0x00];
+
+        let mut out = String::new();
+        for b in byte_tokens(&bytes) {
+            out.push_str(&decoder.step(vec![b]).unwrap());
+        }
+        out.push_str(&decoder.finish().unwrap());
+        assert_eq!(out, "Hi");
+    }
+
+    #[test]
+    fn test_streaming_decoder_splits_surrogate_pair_across_steps() {
+        let mut decoder = StreamingUTF16Decoder::new(UTF16ByteLevel::default());
+        // 😀 = U+1F600, surrogate pair bytes 0x3D 0xD8 0x00 0xDE, split after the high
+        // surrogate so it must be held over to the next step.
+        let high = byte_tokens(&[0x3D, 0xD8]);
+        let low = byte_tokens(&[0x00, 0xDE]);
+
+        let first = decoder.step(high).unwrap();
+        assert_eq!(first, "");
+        let second = decoder.step(low).unwrap();
+        assert_eq!(second, "😀");
+        assert_eq!(decoder.finish().unwrap(), "");
+    }
+
+    #[test]
+    fn test_streaming_decoder_finish_flushes_dangling_surrogate_lossily() {
+        let mut decoder =
+            StreamingUTF16Decoder::new(UTF16ByteLevel::default().lossy(true));
+        let high = byte_tokens(&[0x3D, 0xD8]);
+
+        assert_eq!(decoder.step(high).unwrap(), "");
+        assert_eq!(decoder.finish().unwrap(), "\u{FFFD}");
+    }
+
+    #[test]
+    fn test_streaming_decoder_finish_errors_on_dangling_surrogate() {
+        let mut decoder = StreamingUTF16Decoder::new(UTF16ByteLevel::default());
+        let high = byte_tokens(&[0x3D, 0xD8]);
+
+        assert_eq!(decoder.step(high).unwrap(), "");
+        assert!(decoder.finish().is_err());
+    }
+
+    #[test]
+    fn test_streaming_decoder_step_reports_structured_error() {
+        let mut decoder = StreamingUTF16Decoder::new(UTF16ByteLevel::default());
+        // "A" followed immediately by a stray low surrogate, all within one step.
+        let tokens = byte_tokens(&[0x41, 0x00, 0x00, 0xDC]);
+
+        let err = decoder.step(tokens).unwrap_err();
+        let err = err.downcast_ref::<Utf16DecodeError>().unwrap();
+        assert_eq!(err.valid_prefix, "A");
+        assert_eq!(err.valid_up_to, 2);
+        assert_eq!(err.kind, Utf16DecodeErrorKind::UnpairedLowSurrogate);
+    }
+
+    #[test]
+    fn test_streaming_decoder_step_error_offset_is_absolute_across_steps() {
+        let mut decoder = StreamingUTF16Decoder::new(UTF16ByteLevel::default());
+        // First step decodes "Hi" cleanly (4 bytes); the second step's stray low
+        // surrogate must be reported at absolute offset 4, not offset 0 relative to
+        // just the second step's own bytes.
+        assert_eq!(
+            decoder.step(byte_tokens(&[0x48, 0x00, 0x69, 0x00])).unwrap(),
+            "Hi"
+        );
+
+        let err = decoder
+            .step(byte_tokens(&[0x00, 0xDC]))
+            .unwrap_err();
+        let err = err.downcast_ref::<Utf16DecodeError>().unwrap();
+        assert_eq!(err.valid_up_to, 4);
+        assert_eq!(err.kind, Utf16DecodeErrorKind::UnpairedLowSurrogate);
+    }
+
+    #[test]
+    fn test_streaming_decoder_finish_error_offset_is_absolute() {
+        let mut decoder = StreamingUTF16Decoder::new(UTF16ByteLevel::default());
+        assert_eq!(
+            decoder.step(byte_tokens(&[0x48, 0x00, 0x69, 0x00])).unwrap(),
+            "Hi"
+        );
+        // Dangling high surrogate left for `finish`, after 4 bytes already consumed.
+        assert_eq!(decoder.step(byte_tokens(&[0x3D, 0xD8])).unwrap(), "");
+
+        let err = decoder.finish().unwrap_err();
+        let err = err.downcast_ref::<Utf16DecodeError>().unwrap();
+        assert_eq!(err.valid_up_to, 4);
+        assert_eq!(err.kind, Utf16DecodeErrorKind::UnpairedHighSurrogate);
+    }
+
+    #[test]
+    fn test_streaming_decoder_strips_bom_split_across_steps() {
+        let mut decoder = StreamingUTF16Decoder::new(UTF16ByteLevel::default().handle_bom(true));
+        // Little-endian BOM (U+FEFF) followed by "A", fed one byte at a time so the BOM
+        // unit only completes on the second `step` call.
+        let bytes = vec![0xFF, 0xFE, 0x41, 0x00];
+
+        let mut out = String::new();
+        for b in byte_tokens(&bytes) {
+            out.push_str(&decoder.step(vec![b]).unwrap());
+        }
+        out.push_str(&decoder.finish().unwrap());
+        assert_eq!(out, "A");
+    }
+
+    #[test]
+    fn test_streaming_decoder_autodetects_swapped_bom_split_across_steps() {
+        let mut decoder = StreamingUTF16Decoder::new(UTF16ByteLevel::default().handle_bom(true));
+        // Byte-swapped BOM (U+FFFE read little-endian) followed by "A" in big-endian,
+        // fed one byte at a time: the BOM unit only completes on the second `step` call,
+        // and still must flip the byte order for the remainder of the stream.
+        let bytes = vec![0xFE, 0xFF, 0x00, 0x41];
+
+        let mut out = String::new();
+        for b in byte_tokens(&bytes) {
+            out.push_str(&decoder.step(vec![b]).unwrap());
+        }
+        out.push_str(&decoder.finish().unwrap());
+        assert_eq!(out, "A");
+    }
+}
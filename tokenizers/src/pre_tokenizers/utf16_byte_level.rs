@@ -4,23 +4,22 @@ use once_cell::sync::Lazy;
 use crate::utils::SysRegex;
 use serde::{Deserialize, Serialize};
 
+use crate::decoders::utf16_byte_level::ByteOrder;
 use crate::tokenizer::{
     Decoder, Encoding, PostProcessor, PreTokenizedString, PreTokenizer, Result,
     SplitDelimiterBehavior,
 };
-use crate::utils::macro_rules_attribute;
 
-/// Converts UTF-16 bytes to unicode characters for UTF-16 byte level encoding.
-/// Similar to GPT-2's byte level encoding but operates on UTF-16 bytes instead of UTF-8 bytes.
-/// 
+/// Converts bytes to unicode characters for byte level encoding.
+/// Same trick as GPT-2's byte level encoding, just reused across every target encoding
+/// `ByteLevel` supports: the mapping is purely about making each of the 256 byte values
+/// a distinct, visible `char`, independent of what those bytes actually encode.
+///
 /// Author: Hyunsik Kim <avantkim@gmail.com>
 /// Date: May 2025
-/// 
-/// This implementation is based on the original ByteLevel tokenizer from the tokenizers library
-/// but adapted to work with UTF-16 encoding instead of UTF-8.
-/// 
+///
 /// Reference: https://github.com/openai/gpt-2/blob/master/src/encoder.py#L9
-pub(crate) fn utf16_bytes_char() -> HashMap<u8, char> {
+pub(crate) fn bytes_char() -> HashMap<u8, char> {
     let mut bs: Vec<u8> = vec![];
     bs.extend(b'!'..=b'~');
     bs.extend(b'\xA1'..=b'\xAC');
@@ -46,55 +45,348 @@ pub(crate) fn utf16_bytes_char() -> HashMap<u8, char> {
         .collect()
 }
 
-/// Converts UTF-8 string to UTF-16 bytes (little-endian, no BOM)
-/// 
-/// This function takes a UTF-8 string and converts it to UTF-16 little-endian encoding
+/// Streams the UTF-16 byte-level bytes of `text`, one byte at a time, without
+/// allocating an intermediate `Vec<u16>` or `Vec<u8>`: each UTF-16 code unit from
+/// `str::encode_utf16` is split into its two bytes on the fly per `byte_order`. This
+/// matches the iterator-returning style of `str::encode_utf16` itself, so callers on a
+/// hot path can consume bytes directly instead of collecting them first.
+pub fn encode_utf16_bytes(text: &str, byte_order: ByteOrder) -> impl Iterator<Item = u8> + '_ {
+    text.encode_utf16().flat_map(move |unit| {
+        match byte_order {
+            ByteOrder::LittleEndian => unit.to_le_bytes(),
+            ByteOrder::BigEndian => unit.to_be_bytes(),
+        }
+    })
+}
+
+/// Converts UTF-8 string to UTF-16 bytes using the given byte order (no BOM)
+///
+/// This function takes a UTF-8 string and converts it to UTF-16 encoding
 /// without a BOM (Byte Order Mark). The resulting bytes are what will be processed
 /// by the BPE algorithm.
-/// 
+///
 /// Args:
 ///     text: UTF-8 string to convert
-/// 
+///     byte_order: whether to emit each code unit little-endian or big-endian
+///
 /// Returns:
-///     Vector of bytes representing the UTF-16 little-endian encoding
-pub(crate) fn utf8_to_utf16_bytes(text: &str) -> Vec<u8> {
-    let utf16_units: Vec<u16> = text.encode_utf16().collect();
-    let mut bytes = Vec::with_capacity(utf16_units.len() * 2);
-    
-    for unit in utf16_units {
-        // Little-endian encoding
-        bytes.push((unit & 0xFF) as u8);
-        bytes.push((unit >> 8) as u8);
-    }
-    
-    bytes
+///     Vector of bytes representing the UTF-16 encoding
+pub(crate) fn utf8_to_utf16_bytes(text: &str, byte_order: ByteOrder) -> Vec<u8> {
+    encode_utf16_bytes(text, byte_order).collect()
 }
 
-/// Converts UTF-16 bytes back to UTF-8 string
-/// 
-/// This function takes UTF-16 bytes (little-endian, no BOM) and converts them back
-/// to a UTF-8 string. This is used during decoding.
-/// 
-/// Args:
-///     bytes: Vector of bytes representing UTF-16 little-endian encoding
-/// 
-/// Returns:
-///     Result containing the UTF-8 string or an error if the bytes are invalid
-pub(crate) fn utf16_bytes_to_utf8(bytes: &[u8]) -> Result<String> {
-    if bytes.len() % 2 != 0 {
-        return Err("Invalid UTF-16 byte sequence: odd number of bytes".into());
-    }
-    
-    let mut utf16_units = Vec::with_capacity(bytes.len() / 2);
-    
-    for chunk in bytes.chunks_exact(2) {
-        // Little-endian decoding
-        let unit = u16::from_le_bytes([chunk[0], chunk[1]]);
-        utf16_units.push(unit);
-    }
-    
-    String::from_utf16(&utf16_units)
-        .map_err(|e| format!("Invalid UTF-16 sequence: {}", e).into())
+/// Returns the 2-byte BOM (U+FEFF) encoded in the given byte order.
+fn bom_bytes(byte_order: ByteOrder) -> [u8; 2] {
+    match byte_order {
+        ByteOrder::LittleEndian => [0xFF, 0xFE],
+        ByteOrder::BigEndian => [0xFE, 0xFF],
+    }
+}
+
+/// A target encoding that `ByteLevel` can tokenize at the byte level.
+///
+/// The byte↔visible-char alphabet trick and the GPT-2 regex split are the same no
+/// matter what the underlying encoding is; only how a `char` turns into bytes (and
+/// back) differs. Implementing this trait is all that's needed to add a new target
+/// encoding to `ByteLevel`.
+pub trait ByteEncoding {
+    /// Encodes a UTF-8 string slice into this encoding's raw bytes.
+    fn encode(&self, text: &str) -> Vec<u8>;
+    /// Streams this encoding's raw bytes for `text` one at a time, so a hot loop (e.g.
+    /// the pre-tokenizer's `normalize` step) can consume them without collecting an
+    /// intermediate `Vec<u8>` first. The default falls back to `encode`; implementations
+    /// that can stream directly from `text` should override this to skip that buffer.
+    fn encode_bytes<'a>(&'a self, text: &'a str) -> Box<dyn Iterator<Item = u8> + 'a> {
+        Box::new(self.encode(text).into_iter())
+    }
+    /// Decodes this encoding's raw bytes back into a UTF-8 string.
+    fn decode(&self, bytes: &[u8]) -> Result<String>;
+    /// Number of bytes per code unit in this encoding (e.g. 1 for UTF-8, 2 for UTF-16).
+    fn unit_size(&self) -> usize;
+    /// Bytes to prepend to the very first pre-tokenized piece of a document, or `None`
+    /// if this encoding doesn't use a byte-order mark. Defaults to `None`.
+    fn bom_prefix(&self) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+/// Byte-level tokenization over raw UTF-8 bytes, i.e. the original GPT-2 scheme.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Utf8Encoding;
+
+// Serialized by hand, rather than derived, so this (and every other `ByteEncoding`)
+// carries its own internally-tagged `type` field and flattens onto `ByteLevel<E>`'s own
+// fields instead of nesting under an `encoding` key — the same flat, tagged shape
+// `impl_serde_type!` gives the sibling pre-tokenizers in this module family, and the one
+// a saved `UTF16ByteLevel` tokenizer already relies on.
+impl Serialize for Utf8Encoding {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        #[derive(Serialize)]
+        struct Tagged {
+            r#type: &'static str,
+        }
+        Tagged { r#type: "ByteLevel" }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Utf8Encoding {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Tagged {
+            #[serde(default, rename = "type")]
+            _type: Option<String>,
+        }
+        Tagged::deserialize(deserializer)?;
+        Ok(Utf8Encoding)
+    }
+}
+
+impl ByteEncoding for Utf8Encoding {
+    fn encode(&self, text: &str) -> Vec<u8> {
+        text.as_bytes().to_vec()
+    }
+
+    fn encode_bytes<'a>(&'a self, text: &'a str) -> Box<dyn Iterator<Item = u8> + 'a> {
+        Box::new(text.bytes())
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<String> {
+        String::from_utf8(bytes.to_vec()).map_err(|e| e.into())
+    }
+
+    fn unit_size(&self) -> usize {
+        1
+    }
+}
+
+/// Byte-level tokenization over UTF-16 code units, with configurable byte order, an
+/// optional BOM, and a lossy recovery mode.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Utf16Encoding {
+    /// Byte order used for the UTF-16 code units produced on encode and expected on
+    /// decode. Defaults to little-endian to keep existing saved tokenizers loading.
+    pub byte_order: ByteOrder,
+    /// Whether to prepend a BOM (U+FEFF, encoded per `byte_order`) to the start of the
+    /// document on encode.
+    pub emit_bom: bool,
+    /// Whether to detect and consume a leading BOM on decode. A byte-swapped BOM
+    /// (U+FFFE) auto-detects the stream as the opposite of `byte_order`.
+    pub strip_bom: bool,
+    /// When set, decoding recovers from malformed UTF-16 (a split surrogate pair or a
+    /// trailing odd byte) by emitting U+FFFD instead of failing. Useful when decoding a
+    /// single token in isolation, or a stream cut mid-character.
+    pub lossy: bool,
+}
+
+impl Default for Utf16Encoding {
+    fn default() -> Self {
+        Self {
+            byte_order: ByteOrder::LittleEndian,
+            emit_bom: false,
+            strip_bom: false,
+            lossy: false,
+        }
+    }
+}
+
+// Hand-written, like `Utf8Encoding`'s, so the `type` tag and fields land flat on
+// `ByteLevel<Utf16Encoding>` (i.e. `UTF16ByteLevel`) instead of nested under an
+// `encoding` key: this is the exact flat, tagged shape a tokenizer saved before
+// `ByteLevel` was generalized over `E` already has on disk, and it must keep loading.
+impl Serialize for Utf16Encoding {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        #[derive(Serialize)]
+        struct Tagged {
+            r#type: &'static str,
+            byte_order: ByteOrder,
+            emit_bom: bool,
+            strip_bom: bool,
+            lossy: bool,
+        }
+        Tagged {
+            r#type: "UTF16ByteLevel",
+            byte_order: self.byte_order,
+            emit_bom: self.emit_bom,
+            strip_bom: self.strip_bom,
+            lossy: self.lossy,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Utf16Encoding {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Tagged {
+            #[serde(default, rename = "type")]
+            _type: Option<String>,
+            #[serde(default)]
+            byte_order: ByteOrder,
+            #[serde(default)]
+            emit_bom: bool,
+            #[serde(default)]
+            strip_bom: bool,
+            #[serde(default)]
+            lossy: bool,
+        }
+        let t = Tagged::deserialize(deserializer)?;
+        Ok(Self {
+            byte_order: t.byte_order,
+            emit_bom: t.emit_bom,
+            strip_bom: t.strip_bom,
+            lossy: t.lossy,
+        })
+    }
+}
+
+impl ByteEncoding for Utf16Encoding {
+    fn encode(&self, text: &str) -> Vec<u8> {
+        utf8_to_utf16_bytes(text, self.byte_order)
+    }
+
+    fn encode_bytes<'a>(&'a self, text: &'a str) -> Box<dyn Iterator<Item = u8> + 'a> {
+        Box::new(encode_utf16_bytes(text, self.byte_order))
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<String> {
+        // Reuse the decoders module's `utf16_bytes_to_utf8`, which already composes BOM
+        // handling and lossy recovery in one pass, rather than re-deriving that logic
+        // here: a second, independent implementation of the same BOM/lossy composition
+        // is exactly the kind of divergence that let `lossy` silently ignore
+        // `strip_bom` in an earlier version of this encoding.
+        crate::decoders::utf16_byte_level::utf16_bytes_to_utf8(
+            bytes,
+            self.byte_order,
+            self.strip_bom,
+            self.lossy,
+        )
+    }
+
+    fn unit_size(&self) -> usize {
+        2
+    }
+
+    fn bom_prefix(&self) -> Option<Vec<u8>> {
+        self.emit_bom.then(|| bom_bytes(self.byte_order).to_vec())
+    }
+}
+
+/// Byte-level tokenization over UTF-32 code points, i.e. 4 raw bytes per `char` with no
+/// alphabet of its own to learn: a byte-level BPE model can experiment with code-point
+/// granularity directly instead of splitting every character into UTF-8 or UTF-16 bytes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Utf32Encoding {
+    /// Byte order used for each 4-byte code point.
+    pub byte_order: ByteOrder,
+}
+
+impl Default for Utf32Encoding {
+    fn default() -> Self {
+        Self {
+            byte_order: ByteOrder::LittleEndian,
+        }
+    }
+}
+
+// Same hand-written, flat-and-tagged shape as `Utf8Encoding`/`Utf16Encoding`.
+impl Serialize for Utf32Encoding {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        #[derive(Serialize)]
+        struct Tagged {
+            r#type: &'static str,
+            byte_order: ByteOrder,
+        }
+        Tagged {
+            r#type: "UTF32ByteLevel",
+            byte_order: self.byte_order,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Utf32Encoding {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Tagged {
+            #[serde(default, rename = "type")]
+            _type: Option<String>,
+            #[serde(default)]
+            byte_order: ByteOrder,
+        }
+        let t = Tagged::deserialize(deserializer)?;
+        Ok(Self {
+            byte_order: t.byte_order,
+        })
+    }
+}
+
+impl ByteEncoding for Utf32Encoding {
+    fn encode(&self, text: &str) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(text.len() * 4);
+        for ch in text.chars() {
+            let unit = ch as u32;
+            match self.byte_order {
+                ByteOrder::LittleEndian => bytes.extend_from_slice(&unit.to_le_bytes()),
+                ByteOrder::BigEndian => bytes.extend_from_slice(&unit.to_be_bytes()),
+            }
+        }
+        bytes
+    }
+
+    fn encode_bytes<'a>(&'a self, text: &'a str) -> Box<dyn Iterator<Item = u8> + 'a> {
+        let byte_order = self.byte_order;
+        Box::new(text.chars().flat_map(move |ch| {
+            let unit = ch as u32;
+            match byte_order {
+                ByteOrder::LittleEndian => unit.to_le_bytes(),
+                ByteOrder::BigEndian => unit.to_be_bytes(),
+            }
+        }))
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<String> {
+        if bytes.len() % 4 != 0 {
+            return Err("Invalid UTF-32 byte sequence: length is not a multiple of 4".into());
+        }
+
+        let mut decoded = String::with_capacity(bytes.len() / 4);
+        for chunk in bytes.chunks_exact(4) {
+            let unit = match self.byte_order {
+                ByteOrder::LittleEndian => {
+                    u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]])
+                }
+                ByteOrder::BigEndian => {
+                    u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]])
+                }
+            };
+            let ch = char::from_u32(unit)
+                .ok_or_else(|| format!("Invalid UTF-32 code point: {:#X}", unit))?;
+            decoded.push(ch);
+        }
+        Ok(decoded)
+    }
+
+    fn unit_size(&self) -> usize {
+        4
+    }
 }
 
 /// Regex that matches exactly one token.
@@ -104,22 +396,24 @@ static RE: Lazy<SysRegex> = Lazy::new(|| {
         .unwrap()
 });
 
-static UTF16_BYTES_CHAR: Lazy<HashMap<u8, char>> = Lazy::new(utf16_bytes_char);
-static CHAR_UTF16_BYTES: Lazy<HashMap<char, u8>> =
-    Lazy::new(|| utf16_bytes_char().into_iter().map(|(c, b)| (b, c)).collect());
+static BYTES_CHAR: Lazy<HashMap<u8, char>> = Lazy::new(bytes_char);
+static CHAR_BYTES: Lazy<HashMap<char, u8>> =
+    Lazy::new(|| bytes_char().into_iter().map(|(c, b)| (b, c)).collect());
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-/// Provides all the necessary steps to handle the BPE tokenization at the UTF-16 byte-level.
-/// Takes care of all the required processing steps to transform a UTF-8 string to UTF-16 bytes
-/// as needed before and after the BPE model does its job.
-/// 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(serialize = "E: Serialize", deserialize = "E: Deserialize<'de>"))]
+/// Provides all the necessary steps to handle BPE tokenization at the byte level of a
+/// pluggable target encoding `E`. Takes care of all the required processing steps to
+/// transform a UTF-8 string to that encoding's bytes as needed before and after the BPE
+/// model does its job.
+///
 /// Author: Hyunsik Kim <avantkim@gmail.com>
 /// Date: May 2025
-/// 
-/// This is based on the original ByteLevel tokenizer but adapted for UTF-16 encoding.
-#[macro_rules_attribute(impl_serde_type!)]
+///
+/// This is based on the original ByteLevel tokenizer, generalized from its original
+/// hard-coded UTF-16 encoding to any [`ByteEncoding`].
 #[non_exhaustive]
-pub struct UTF16ByteLevel {
+pub struct ByteLevel<E: ByteEncoding> {
     /// Whether to add a leading space to the first word. This allows to treat the leading word
     /// just as any other word.
     pub add_prefix_space: bool,
@@ -130,37 +424,54 @@ pub struct UTF16ByteLevel {
     /// Set it to False if you want to use your own splitting.
     #[serde(default = "default_true")]
     pub use_regex: bool,
+
+    /// The target encoding bytes are produced in on encode and expected in on decode.
+    /// Flattened so its own (hand-tagged) fields land directly on this struct's JSON
+    /// object instead of nesting under an `encoding` key — see `Utf16Encoding`'s
+    /// `Serialize` impl for why.
+    #[serde(flatten)]
+    pub encoding: E,
 }
 
+/// Byte-level tokenization over UTF-16 bytes, with configurable byte order, BOM
+/// handling and lossy decoding. Kept as a type alias of the generalized `ByteLevel` for
+/// backward compatibility with existing code and saved tokenizers.
+pub type UTF16ByteLevel = ByteLevel<Utf16Encoding>;
+
 fn default_true() -> bool {
     true
 }
 
-impl Default for UTF16ByteLevel {
+impl<E: ByteEncoding + Default> Default for ByteLevel<E> {
     fn default() -> Self {
         Self {
             add_prefix_space: true,
             trim_offsets: true,
             use_regex: true,
+            encoding: E::default(),
         }
     }
 }
 
-impl UTF16ByteLevel {
+impl<E: ByteEncoding + Default> ByteLevel<E> {
     pub fn new(add_prefix_space: bool, trim_offsets: bool, use_regex: bool) -> Self {
         Self {
             add_prefix_space,
             trim_offsets,
             use_regex,
+            encoding: E::default(),
         }
     }
+}
 
+impl<E: ByteEncoding> ByteLevel<E> {
     /// Returns the alphabet used by this PreTokenizer.
-    /// Since UTF16ByteLevel works at the byte level on UTF-16 encoded text,
+    /// Since `ByteLevel` works at the byte level of its target encoding,
     /// it encodes each byte value to a unique visible character.
-    /// This means that there is a total of 256 different characters composing this alphabet.
+    /// This means that there is a total of 256 different characters composing this alphabet,
+    /// regardless of the target encoding.
     pub fn alphabet() -> HashSet<char> {
-        UTF16_BYTES_CHAR.values().copied().collect()
+        BYTES_CHAR.values().copied().collect()
     }
 
     #[must_use]
@@ -182,9 +493,51 @@ impl UTF16ByteLevel {
     }
 }
 
-/// As a `PreTokenizer`, `UTF16ByteLevel` is in charge of transforming all the unicode characters
-/// into their UTF-16 byte-level counterpart. It also splits the input according to the configured regex.
-impl PreTokenizer for UTF16ByteLevel {
+impl ByteLevel<Utf16Encoding> {
+    #[must_use]
+    pub fn byte_order(mut self, v: ByteOrder) -> Self {
+        self.encoding.byte_order = v;
+        self
+    }
+
+    #[must_use]
+    pub fn emit_bom(mut self, v: bool) -> Self {
+        self.encoding.emit_bom = v;
+        self
+    }
+
+    #[must_use]
+    pub fn strip_bom(mut self, v: bool) -> Self {
+        self.encoding.strip_bom = v;
+        self
+    }
+
+    #[must_use]
+    pub fn lossy(mut self, v: bool) -> Self {
+        self.encoding.lossy = v;
+        self
+    }
+
+    /// Builds a [`StreamingUTF16Decoder`](crate::decoders::utf16_byte_level::StreamingUTF16Decoder)
+    /// configured to match this pre-tokenizer's byte order, BOM and lossy settings, for
+    /// token-by-token streaming decode. `strip_bom` maps onto the decoder's
+    /// `handle_bom`, since stripping a BOM on decode is the same setting as detecting
+    /// one there.
+    pub fn stream(&self) -> crate::decoders::utf16_byte_level::StreamingUTF16Decoder {
+        crate::decoders::utf16_byte_level::StreamingUTF16Decoder::new(
+            crate::decoders::utf16_byte_level::UTF16ByteLevel {
+                byte_order: self.encoding.byte_order,
+                handle_bom: self.encoding.strip_bom,
+                lossy: self.encoding.lossy,
+            },
+        )
+    }
+}
+
+/// As a `PreTokenizer`, `ByteLevel` is in charge of transforming all the unicode characters
+/// into their target-encoding byte-level counterpart. It also splits the input according to
+/// the configured regex.
+impl<E: ByteEncoding> PreTokenizer for ByteLevel<E> {
     fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> Result<()> {
         let re_ref: &SysRegex = &RE;
         pretokenized.split(|_, mut normalized| {
@@ -197,19 +550,30 @@ impl PreTokenizer for UTF16ByteLevel {
                 Ok(vec![normalized])
             }
         })?;
+        // Tracks whether the BOM has already been emitted, so it only gets prepended
+        // once, to the very first piece of the document, rather than to every split.
+        let bom_emitted = std::cell::Cell::new(false);
         pretokenized.normalize(|normalized| {
             let s = normalized.get();
-            
-            // Convert UTF-8 string to UTF-16 bytes
-            let utf16_bytes = utf8_to_utf16_bytes(s);
-            
-            // Transform each UTF-16 byte to its character representation
-            let mut transformations: Vec<(char, isize)> = Vec::with_capacity(utf16_bytes.len());
-            
-            for (i, &byte) in utf16_bytes.iter().enumerate() {
-                transformations.push((UTF16_BYTES_CHAR[&byte], if i > 0 { 1 } else { 0 }));
+
+            // Stream straight from the target encoding's byte iterator into the
+            // transformation buffer, rather than collecting an intermediate byte `Vec`.
+            let mut transformations: Vec<(char, isize)> =
+                Vec::with_capacity(s.len() * self.encoding.unit_size());
+            let mut i = 0usize;
+            let mut push_byte = |byte: u8| {
+                transformations.push((BYTES_CHAR[&byte], if i > 0 { 1 } else { 0 }));
+                i += 1;
+            };
+
+            if !bom_emitted.replace(true) {
+                if let Some(bom) = self.encoding.bom_prefix() {
+                    bom.into_iter().for_each(&mut push_byte);
+                }
             }
-            
+            self.encoding.encode_bytes(s).for_each(&mut push_byte);
+            drop(push_byte);
+
             // Apply the transformations to convert the original string to byte-level representation
             normalized.transform(transformations, 0);
             Ok(())
@@ -217,34 +581,40 @@ impl PreTokenizer for UTF16ByteLevel {
     }
 }
 
-/// As a `Decoder`, `UTF16ByteLevel` is in charge of converting any UTF-16 byte-level characters
+/// Maps a token's chars back to the raw bytes they represent, via `CHAR_BYTES`.
+/// Characters outside the byte-level alphabet (e.g. added special tokens) are passed
+/// through as their own UTF-8 bytes, unchanged.
+fn token_to_bytes(token: &str) -> Vec<u8> {
+    token
+        .chars()
+        .try_fold(vec![], |mut acc, c| {
+            CHAR_BYTES.get(&c).map(|b| {
+                acc.push(*b);
+                acc
+            })
+        })
+        .unwrap_or_else(|| token.as_bytes().to_vec())
+}
+
+/// As a `Decoder`, `ByteLevel` is in charge of converting any byte-level characters
 /// to their unicode counterpart, before merging everything back into a single String.
 /// This decoder will consume the tokens and merge them in one step to alleviate
 /// the fact that single token decoded might be a byte not representable as a String.
-impl Decoder for UTF16ByteLevel {
+impl<E: ByteEncoding> Decoder for ByteLevel<E> {
     fn decode_chain(&self, tokens: Vec<String>) -> Result<Vec<String>> {
-        let utf16_bytes = tokens
-            .into_iter()
-            .flat_map(|t| {
-                t.chars()
-                    .try_fold(vec![], |mut acc, c| {
-                        CHAR_UTF16_BYTES.get(&c).map(|b| {
-                            acc.push(*b);
-                            acc
-                        })
-                    })
-                    .unwrap_or_else(|| t.as_bytes().to_vec())
-            })
+        let bytes = tokens
+            .iter()
+            .flat_map(|t| token_to_bytes(t))
             .collect::<Vec<u8>>();
-            
-        // Convert UTF-16 bytes back to UTF-8 string
-        let decoded = utf16_bytes_to_utf8(&utf16_bytes)?;
+
+        // Convert the target encoding's bytes back to a UTF-8 string
+        let decoded = self.encoding.decode(&bytes)?;
         Ok(vec![decoded])
     }
 }
 
-/// As a `PostProcessor`, `UTF16ByteLevel` is in charge of trimming the offsets if necessary.
-impl PostProcessor for UTF16ByteLevel {
+/// As a `PostProcessor`, `ByteLevel` is in charge of trimming the offsets if necessary.
+impl<E: ByteEncoding> PostProcessor for ByteLevel<E> {
     fn added_tokens(&self, _is_pair: bool) -> usize {
         0
     }
@@ -256,11 +626,11 @@ impl PostProcessor for UTF16ByteLevel {
     ) -> Result<Vec<Encoding>> {
         if self.trim_offsets {
             for encoding in encodings.iter_mut() {
-                process_utf16_offsets(encoding, self.add_prefix_space);
+                process_byte_level_offsets(encoding, self.add_prefix_space);
                 encoding
                     .get_overflowing_mut()
                     .iter_mut()
-                    .for_each(|encoding| process_utf16_offsets(encoding, self.add_prefix_space));
+                    .for_each(|encoding| process_byte_level_offsets(encoding, self.add_prefix_space));
             }
         }
         for (i, encoding) in encodings.iter_mut().enumerate() {
@@ -270,18 +640,18 @@ impl PostProcessor for UTF16ByteLevel {
     }
 }
 
-/// Process offsets for UTF-16 byte level encoding
-/// This function adjusts offsets to account for the UTF-16 byte level transformation
-pub fn process_utf16_offsets(encoding: &mut Encoding, add_prefix_space: bool) {
+/// Process offsets for byte level encoding
+/// This function adjusts offsets to account for the byte level transformation
+pub fn process_byte_level_offsets(encoding: &mut Encoding, add_prefix_space: bool) {
     encoding.process_tokens_with_offsets_mut(|(i, (token, offsets))| {
         let mut leading_spaces = token
             .chars()
-            .take_while(|c| *c == UTF16_BYTES_CHAR[&b' '] || c.is_whitespace())
+            .take_while(|c| *c == BYTES_CHAR[&b' '] || c.is_whitespace())
             .count();
         let trailing_spaces = token
             .chars()
             .rev()
-            .take_while(|c| *c == UTF16_BYTES_CHAR[&b' '] || c.is_whitespace())
+            .take_while(|c| *c == BYTES_CHAR[&b' '] || c.is_whitespace())
             .count();
 
         if leading_spaces > 0 || trailing_spaces > 0 {
@@ -309,31 +679,39 @@ pub fn process_utf16_offsets(encoding: &mut Encoding, add_prefix_space: bool) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{OffsetReferential, OffsetType};
 
     #[test]
     fn test_utf8_to_utf16_bytes() {
         // Test ASCII
         let ascii = "Hello";
-        let bytes = utf8_to_utf16_bytes(ascii);
+        let bytes = utf8_to_utf16_bytes(ascii, ByteOrder::LittleEndian);
         assert_eq!(bytes, vec![0x48, 0x00, 0x65, 0x00, 0x6C, 0x00, 0x6C, 0x00, 0x6F, 0x00]);
-        
+
         // Test Korean characters
         let korean = "가나다";
-        let bytes = utf8_to_utf16_bytes(korean);
+        let bytes = utf8_to_utf16_bytes(korean, ByteOrder::LittleEndian);
         // 가 = U+AC00, 나 = U+B098, 다 = U+B2E4
         assert_eq!(bytes, vec![0x00, 0xAC, 0x98, 0xB0, 0xE4, 0xB2]);
     }
 
+    #[test]
+    fn test_utf8_to_utf16_bytes_big_endian() {
+        let bytes = utf8_to_utf16_bytes("Hello", ByteOrder::BigEndian);
+        assert_eq!(bytes, vec![0x00, 0x48, 0x00, 0x65, 0x00, 0x6C, 0x00, 0x6C, 0x00, 0x6F]);
+    }
+
     #[test]
     fn test_utf16_bytes_to_utf8() {
+        let encoding = Utf16Encoding::default();
         // Test ASCII
         let bytes = vec![0x48, 0x00, 0x65, 0x00, 0x6C, 0x00, 0x6C, 0x00, 0x6F, 0x00];
-        let result = utf16_bytes_to_utf8(&bytes).unwrap();
+        let result = encoding.decode(&bytes).unwrap();
         assert_eq!(result, "Hello");
-        
+
         // Test Korean characters
         let bytes = vec![0x00, 0xAC, 0x98, 0xB0, 0xE4, 0xB2];
-        let result = utf16_bytes_to_utf8(&bytes).unwrap();
+        let result = encoding.decode(&bytes).unwrap();
         assert_eq!(result, "가나다");
     }
 
@@ -346,10 +724,26 @@ mod tests {
             "🌍🌎🌏",
             "Hello 안녕하세요 你好",
         ];
-        
+
+        let encoding = Utf16Encoding::default();
         for s in test_strings {
-            let bytes = utf8_to_utf16_bytes(s);
-            let recovered = utf16_bytes_to_utf8(&bytes).unwrap();
+            let bytes = encoding.encode(s);
+            let recovered = encoding.decode(&bytes).unwrap();
+            assert_eq!(s, recovered);
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_big_endian() {
+        let test_strings = vec!["Hello World", "안녕하세요", "🌍🌎🌏"];
+
+        let encoding = Utf16Encoding {
+            byte_order: ByteOrder::BigEndian,
+            ..Default::default()
+        };
+        for s in test_strings {
+            let bytes = encoding.encode(s);
+            let recovered = encoding.decode(&bytes).unwrap();
             assert_eq!(s, recovered);
         }
     }
@@ -359,4 +753,271 @@ mod tests {
         let alphabet = UTF16ByteLevel::alphabet();
         assert_eq!(alphabet.len(), 256);
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_strip_bom_little_endian() {
+        let encoding = Utf16Encoding {
+            strip_bom: true,
+            ..Default::default()
+        };
+        // U+FEFF BOM followed by "Hi" in little-endian.
+        let mut bytes = bom_bytes(ByteOrder::LittleEndian).to_vec();
+        bytes.extend(encoding.encode("Hi"));
+        assert_eq!(encoding.decode(&bytes).unwrap(), "Hi");
+    }
+
+    #[test]
+    fn test_strip_bom_autodetects_swapped_endianness() {
+        // A big-endian BOM (0xFE 0xFF) followed by "Hi" encoded big-endian, but the
+        // configured byte order is little-endian: the byte-swapped BOM should flip
+        // decoding to big-endian for the remainder of the stream.
+        let encoding = Utf16Encoding {
+            strip_bom: true,
+            ..Default::default()
+        };
+        let mut bytes = bom_bytes(ByteOrder::BigEndian).to_vec();
+        bytes.extend(utf8_to_utf16_bytes("Hi", ByteOrder::BigEndian));
+        assert_eq!(encoding.decode(&bytes).unwrap(), "Hi");
+    }
+
+    #[test]
+    fn test_lossy_dangling_high_surrogate() {
+        let encoding = Utf16Encoding {
+            lossy: true,
+            ..Default::default()
+        };
+        // High surrogate (0xD800) with no low surrogate following, then "A".
+        let bytes = vec![0x00, 0xD8, 0x41, 0x00];
+        assert_eq!(encoding.decode(&bytes).unwrap(), "\u{FFFD}A");
+    }
+
+    #[test]
+    fn test_lossy_stray_low_surrogate() {
+        let encoding = Utf16Encoding {
+            lossy: true,
+            ..Default::default()
+        };
+        // Low surrogate (0xDC00) with no preceding high surrogate.
+        let bytes = vec![0x41, 0x00, 0x00, 0xDC];
+        assert_eq!(encoding.decode(&bytes).unwrap(), "A\u{FFFD}");
+    }
+
+    #[test]
+    fn test_lossy_trailing_odd_byte() {
+        let encoding = Utf16Encoding {
+            lossy: true,
+            ..Default::default()
+        };
+        let bytes = vec![0x41, 0x00, 0x42];
+        assert_eq!(encoding.decode(&bytes).unwrap(), "A\u{FFFD}");
+    }
+
+    #[test]
+    fn test_lossy_valid_surrogate_pair_still_decodes() {
+        let encoding = Utf16Encoding {
+            lossy: true,
+            ..Default::default()
+        };
+        // 😀 = U+1F600, surrogate pair 0xD83D 0xDE00
+        let bytes = vec![0x3D, 0xD8, 0x00, 0xDE];
+        assert_eq!(encoding.decode(&bytes).unwrap(), "😀");
+    }
+
+    #[test]
+    fn test_lossy_and_strip_bom_compose() {
+        // A reasonable combination for robust streaming decode: `lossy` must not
+        // silently ignore `strip_bom`, since both now go through the same composed
+        // `decoders::utf16_byte_level::utf16_bytes_to_utf8` call.
+        let encoding = Utf16Encoding {
+            lossy: true,
+            strip_bom: true,
+            ..Default::default()
+        };
+        let mut bytes = bom_bytes(ByteOrder::LittleEndian).to_vec();
+        bytes.extend(vec![0x41, 0x00, 0x00, 0xD8]); // "A" then a dangling high surrogate
+        assert_eq!(encoding.decode(&bytes).unwrap(), "A\u{FFFD}");
+    }
+
+    #[test]
+    fn test_decode_chain_lossy_mode_never_fails() {
+        let decoder = UTF16ByteLevel::default().lossy(true);
+        // A dangling high surrogate, encoded as its byte-level character tokens.
+        let tokens = vec![
+            BYTES_CHAR[&0x00].to_string(),
+            BYTES_CHAR[&0xD8].to_string(),
+        ];
+        let decoded = decoder.decode_chain(tokens).unwrap();
+        assert_eq!(decoded, vec!["\u{FFFD}".to_string()]);
+    }
+
+    /// Maps a run of bytes to a single token string of their byte-level character
+    /// representations, for exercising `stream()` one token at a time.
+    fn bytes_to_token(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| BYTES_CHAR[b]).collect()
+    }
+
+    #[test]
+    fn test_stream_splits_mid_code_unit() {
+        let mut stream = UTF16ByteLevel::default().stream();
+        // "Hi" = [0x48, 0x00, 0x69, 0x00], fed one byte at a time.
+        let mut out = String::new();
+        for byte in [0x48, 0x00, 0x69, 0x00] {
+            out.push_str(&stream.step(vec![bytes_to_token(&[byte])]).unwrap());
+        }
+        out.push_str(&stream.finish().unwrap());
+        assert_eq!(out, "Hi");
+    }
+
+    #[test]
+    fn test_stream_splits_surrogate_pair_across_steps() {
+        let mut stream = UTF16ByteLevel::default().stream();
+        // 😀 = U+1F600, surrogate pair bytes 0x3D 0xD8 0x00 0xDE, split after the high
+        // surrogate so it must be held over to the next step.
+        let high = bytes_to_token(&[0x3D, 0xD8]);
+        let low = bytes_to_token(&[0x00, 0xDE]);
+
+        assert_eq!(stream.step(vec![high]).unwrap(), "");
+        assert_eq!(stream.step(vec![low]).unwrap(), "😀".to_string());
+        assert_eq!(stream.finish().unwrap(), "");
+    }
+
+    #[test]
+    fn test_stream_finish_flushes_dangling_surrogate_lossily() {
+        let mut stream = UTF16ByteLevel::default().lossy(true).stream();
+        let high = bytes_to_token(&[0x3D, 0xD8]);
+
+        assert_eq!(stream.step(vec![high]).unwrap(), "");
+        assert_eq!(stream.finish().unwrap(), "\u{FFFD}");
+    }
+
+    #[test]
+    fn test_stream_finish_errors_on_dangling_surrogate_by_default() {
+        let mut stream = UTF16ByteLevel::default().stream();
+        let high = bytes_to_token(&[0x3D, 0xD8]);
+
+        assert_eq!(stream.step(vec![high]).unwrap(), "");
+        assert!(stream.finish().is_err());
+    }
+
+    #[test]
+    fn test_emit_bom() {
+        let pretok = UTF16ByteLevel::default().emit_bom(true);
+        let mut pretokenized = PreTokenizedString::from("Hi");
+        pretok.pre_tokenize(&mut pretokenized).unwrap();
+        let decoded_bytes = pretokenized
+            .get_splits(OffsetReferential::Original, OffsetType::Byte)
+            .into_iter()
+            .flat_map(|(s, _, _)| s.chars().map(|c| CHAR_BYTES[&c]))
+            .collect::<Vec<u8>>();
+        assert_eq!(&decoded_bytes[..2], &bom_bytes(ByteOrder::LittleEndian));
+    }
+
+    #[test]
+    fn test_utf8_encoding_roundtrip() {
+        let pretok = ByteLevel::<Utf8Encoding>::default();
+        let mut pretokenized = PreTokenizedString::from("Hello World");
+        pretok.pre_tokenize(&mut pretokenized).unwrap();
+
+        let tokens = pretokenized
+            .get_splits(OffsetReferential::Original, OffsetType::Byte)
+            .into_iter()
+            .map(|(s, _, _)| s.to_string())
+            .collect::<Vec<_>>();
+        let decoded = pretok.decode_chain(tokens).unwrap();
+        assert_eq!(decoded, vec![" Hello World".to_string()]);
+    }
+
+    #[test]
+    fn test_utf32_encoding_roundtrip() {
+        let encoding = Utf32Encoding::default();
+        let bytes = encoding.encode("Hi😀");
+        let recovered = encoding.decode(&bytes).unwrap();
+        assert_eq!(recovered, "Hi😀");
+        assert_eq!(bytes.len(), "Hi😀".chars().count() * 4);
+    }
+
+    #[test]
+    fn test_encode_utf16_bytes_matches_collected_vec() {
+        for (text, byte_order) in [
+            ("Hello World", ByteOrder::LittleEndian),
+            ("안녕하세요", ByteOrder::BigEndian),
+            ("🌍🌎🌏", ByteOrder::LittleEndian),
+        ] {
+            let streamed: Vec<u8> = encode_utf16_bytes(text, byte_order).collect();
+            assert_eq!(streamed, utf8_to_utf16_bytes(text, byte_order));
+        }
+    }
+
+    #[test]
+    fn test_encode_bytes_matches_encode_for_every_encoding() {
+        let text = "Hello 안녕 🌍";
+
+        let utf8 = Utf8Encoding;
+        assert_eq!(
+            utf8.encode_bytes(text).collect::<Vec<u8>>(),
+            utf8.encode(text)
+        );
+
+        for byte_order in [ByteOrder::LittleEndian, ByteOrder::BigEndian] {
+            let utf16 = Utf16Encoding {
+                byte_order,
+                ..Default::default()
+            };
+            assert_eq!(
+                utf16.encode_bytes(text).collect::<Vec<u8>>(),
+                utf16.encode(text)
+            );
+
+            let utf32 = Utf32Encoding { byte_order };
+            assert_eq!(
+                utf32.encode_bytes(text).collect::<Vec<u8>>(),
+                utf32.encode(text)
+            );
+        }
+    }
+
+    #[test]
+    fn test_utf32_encoding_big_endian() {
+        let encoding = Utf32Encoding {
+            byte_order: ByteOrder::BigEndian,
+        };
+        let bytes = encoding.encode("A");
+        assert_eq!(bytes, vec![0x00, 0x00, 0x00, 0x41]);
+        assert_eq!(encoding.decode(&bytes).unwrap(), "A");
+    }
+
+    #[test]
+    fn test_utf16_byte_level_serializes_flat_with_type_tag() {
+        // `encoding`'s fields must land flat on the outer object, tagged by `type`,
+        // rather than nested under an `encoding` key: this is the shape a tokenizer
+        // saved before `ByteLevel` was generalized over `E` already has on disk.
+        let pretok = UTF16ByteLevel::default().byte_order(ByteOrder::BigEndian);
+        let value = serde_json::to_value(&pretok).unwrap();
+        assert_eq!(value["type"], "UTF16ByteLevel");
+        assert_eq!(value["byte_order"], "BigEndian");
+        assert_eq!(value["emit_bom"], false);
+        assert_eq!(value["strip_bom"], false);
+        assert_eq!(value["lossy"], false);
+        assert!(value.get("encoding").is_none());
+    }
+
+    #[test]
+    fn test_utf16_byte_level_loads_pre_generalization_json() {
+        // The flat shape `UTF16ByteLevel` shipped before it became `ByteLevel<Utf16Encoding>`.
+        let json = r#"{
+            "type": "UTF16ByteLevel",
+            "add_prefix_space": true,
+            "trim_offsets": true,
+            "use_regex": true,
+            "byte_order": "LittleEndian",
+            "emit_bom": false,
+            "strip_bom": true,
+            "lossy": false
+        }"#;
+        let pretok: UTF16ByteLevel = serde_json::from_str(json).unwrap();
+        assert_eq!(pretok.encoding.byte_order, ByteOrder::LittleEndian);
+        assert!(pretok.encoding.strip_bom);
+        assert!(!pretok.encoding.emit_bom);
+        assert!(!pretok.encoding.lossy);
+    }
+}
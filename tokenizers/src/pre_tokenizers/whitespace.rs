@@ -1,30 +1,145 @@
 use once_cell::sync::Lazy;
 
 use regex::Regex;
+use unicode_categories::UnicodeCategories;
+use unicode_segmentation::UnicodeSegmentation;
 
 use crate::tokenizer::{
-    pattern::Invert, PreTokenizedString, PreTokenizer, Result, SplitDelimiterBehavior,
+    pattern::{Invert, Pattern},
+    Offsets, PreTokenizedString, PreTokenizer, Result, SplitDelimiterBehavior,
 };
 use crate::utils::macro_rules_attribute;
 
+/// How `Whitespace` decides where one pre-token ends and the next begins.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum SplitMode {
+    /// The original `\w+|[^\w\s]+` regex split.
+    Regex,
+    /// Classify each character by Unicode general category (Letter / Number /
+    /// Punctuation / Symbol / Separator) and split on category transitions, keeping
+    /// grapheme clusters (combining marks, ZWJ sequences) attached to their base.
+    UnicodeCategory,
+}
+
+impl Default for SplitMode {
+    fn default() -> Self {
+        SplitMode::Regex
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[macro_rules_attribute(impl_serde_type!)]
-pub struct Whitespace;
+pub struct Whitespace {
+    #[serde(default)]
+    pub split_mode: SplitMode,
+}
 
 impl Default for Whitespace {
     fn default() -> Self {
-        Self
+        Self {
+            split_mode: SplitMode::Regex,
+        }
+    }
+}
+
+impl Whitespace {
+    #[must_use]
+    pub fn split_mode(mut self, v: SplitMode) -> Self {
+        self.split_mode = v;
+        self
+    }
+}
+
+/// Classifies each grapheme cluster of the input by the Unicode general category of its
+/// base character, and reports one match per run of same-category grapheme clusters.
+/// Using grapheme clusters rather than raw chars keeps combining marks and ZWJ-joined
+/// sequences (e.g. the flag emoji `🏳️‍🌈`) attached to their base character instead of
+/// being split apart.
+struct UnicodeCategorySplit;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum CharCategory {
+    Letter,
+    Number,
+    Punctuation,
+    Symbol,
+    Separator,
+    Other,
+}
+
+fn classify(c: char) -> CharCategory {
+    if c.is_letter() {
+        CharCategory::Letter
+    } else if c.is_number() {
+        CharCategory::Number
+    } else if c.is_punctuation() {
+        CharCategory::Punctuation
+    } else if c.is_symbol() {
+        CharCategory::Symbol
+    } else if c.is_separator() || c.is_whitespace() {
+        // `is_separator()` only covers the Unicode Zs/Zl/Zp categories, not the Cc
+        // control characters (tab, newline, carriage return, ...). Folding in
+        // `is_whitespace()` keeps those treated as whitespace too, matching the `\s`
+        // semantics the `Regex` split mode relies on.
+        CharCategory::Separator
+    } else {
+        CharCategory::Other
+    }
+}
+
+impl Pattern for UnicodeCategorySplit {
+    fn find_matches(&self, inside: &str) -> Result<Vec<(Offsets, bool)>> {
+        // `SplitDelimiterBehavior::Removed` drops ranges marked `true` and keeps the
+        // rest, so a run is marked `true` (removed) exactly when it is pure Separator
+        // (whitespace), matching the original regex mode which never emits whitespace
+        // as its own pre-token.
+        if inside.is_empty() {
+            return Ok(vec![((0, 0), false)]);
+        }
+
+        let mut matches = Vec::new();
+        let mut run_start = 0usize;
+        let mut run_category: Option<CharCategory> = None;
+
+        for (idx, grapheme) in inside.grapheme_indices(true) {
+            let base_char = grapheme
+                .chars()
+                .next()
+                .expect("grapheme clusters are never empty");
+            let category = classify(base_char);
+
+            match run_category {
+                Some(c) if c == category => {}
+                Some(c) => {
+                    matches.push(((run_start, idx), c == CharCategory::Separator));
+                    run_start = idx;
+                }
+                None => run_start = idx,
+            }
+            run_category = Some(category);
+        }
+        let last_category = run_category.expect("inside is non-empty");
+        matches.push(((run_start, inside.len()), last_category == CharCategory::Separator));
+
+        Ok(matches)
     }
 }
 
 impl PreTokenizer for Whitespace {
     fn pre_tokenize(&self, pretokenized: &mut PreTokenizedString) -> Result<()> {
-        static RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\w+|[^\w\s]+").unwrap());
-        let re_ref: &Regex = &RE;
+        match self.split_mode {
+            SplitMode::Regex => {
+                static RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\w+|[^\w\s]+").unwrap());
+                let re_ref: &Regex = &RE;
 
-        pretokenized.split(|_, normalized| {
-            normalized.split(Invert(re_ref), SplitDelimiterBehavior::Removed)
-        })
+                pretokenized.split(|_, normalized| {
+                    normalized.split(Invert(re_ref), SplitDelimiterBehavior::Removed)
+                })
+            }
+            SplitMode::UnicodeCategory => pretokenized.split(|_, normalized| {
+                normalized.split(UnicodeCategorySplit, SplitDelimiterBehavior::Removed)
+            }),
+        }
     }
 }
 
@@ -64,7 +179,36 @@ mod tests {
             ),
             ("\n", vec![]),
         ];
-        let pretok = Whitespace {};
+        let pretok = Whitespace::default();
+        for (s, res) in tests {
+            let mut pretokenized = PreTokenizedString::from(s);
+            pretok.pre_tokenize(&mut pretokenized).unwrap();
+            assert_eq!(
+                pretokenized
+                    .get_splits(OffsetReferential::Original, OffsetType::Byte)
+                    .into_iter()
+                    .map(|(s, o, _)| (s, o))
+                    .collect::<Vec<_>>(),
+                res
+            );
+        }
+    }
+
+    #[test]
+    fn unicode_category_split() {
+        let tests = vec![
+            (
+                "Hey man!",
+                vec![("Hey", (0, 3)), ("man", (4, 7)), ("!", (7, 8))],
+            ),
+            ("안녕하세요 world", vec![("안녕하세요", (0, 15)), ("world", (16, 21))]),
+            ("\n", vec![]),
+            ("a\tb", vec![("a", (0, 1)), ("b", (2, 3))]),
+            // The ZWJ-joined rainbow flag must stay a single grapheme cluster / token,
+            // rather than being split into its component codepoints.
+            ("🏳️‍🌈", vec![("🏳️‍🌈", (0, 14))]),
+        ];
+        let pretok = Whitespace::default().split_mode(SplitMode::UnicodeCategory);
         for (s, res) in tests {
             let mut pretokenized = PreTokenizedString::from(s);
             pretok.pre_tokenize(&mut pretokenized).unwrap();